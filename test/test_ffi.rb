@@ -82,6 +82,59 @@ class UdonFFITest < Minitest::Test
     assert_equal ["highlight"], events[0][:classes]
   end
 
+  def test_element_suffix_positions
+    # FULL-SPEC.md "Element Suffixes": every allowed position expands to :'?' true
+    ["|name?\n", "|name?[id]\n", "|name?[id].class\n", "|name[id]?\n",
+     "|name[id]? .class\n", "|name[id].class ?\n"].each do |input|
+      events = Udon.parse(input)
+      assert_equal "name", events[0][:name], input
+      suffix = events.find { |e| e[:type] == :attribute && e[:key] == "?" }
+      refute_nil suffix, "expected :'?' attribute for #{input.inspect}"
+      assert_equal true, suffix[:value], input
+      assert_equal "id", events[0][:id], input if input.include?("[id]")
+      assert_equal ["class"], events[0][:classes], input if input.include?(".class")
+    end
+  end
+
+  def test_element_suffix_kinds
+    %w[? ! * +].each do |suffix|
+      events = Udon.parse("|field[name]#{suffix}\n")
+      attr = events.find { |e| e[:type] == :attribute }
+      assert_equal suffix, attr[:key]
+      assert_equal true, attr[:value]
+    end
+  end
+
+  def test_element_suffix_on_class_is_reserved
+    events = Udon.parse("|name[id].class?\n")
+    assert events.any? { |e| e[:type] == :error }, "Expected error for suffix on class"
+  end
+
+  def test_anonymous_element_with_suffix
+    events = Udon.parse("|[hero]!.banner\n")
+    assert_nil events[0][:name]
+    assert_equal "hero", events[0][:id]
+    assert_equal ["banner"], events[0][:classes]
+    assert_equal "!", events.find { |e| e[:type] == :attribute }[:key]
+  end
+
+  def test_schema_dsl_required_field
+    events = Udon.parse("|str[username]!\n")
+    assert_equal "str", events[0][:name]
+    assert_equal "username", events[0][:id]
+    assert_equal "!", events[1][:key]
+    assert_equal true, events[1][:value]
+  end
+
+  def test_parse_schema_dsl_example
+    path = File.expand_path('../examples/schema-dsl.udon', __dir__)
+    skip "schema-dsl.udon not found" unless File.exist?(path)
+
+    events = Udon.parse(File.read(path))
+    errors = events.select { |e| e[:type] == :error }
+    assert_empty errors, errors.map { |e| "#{e[:message]} at #{e[:span]}" }.join("\n")
+  end
+
   def test_element_with_inline_content
     events = Udon.parse("|h1 Hello, World!\n")
     types = events.map { |e| e[:type] }
@@ -99,6 +152,55 @@ class UdonFFITest < Minitest::Test
     assert_equal "container", events[1][:value]
   end
 
+  def test_complex_attribute_value
+    input = <<~UDON
+      |api
+        :headers
+          |header[content-type] :value application/json
+          |header[auth] :value Bearer
+        |body
+    UDON
+    types = Udon.parse(input).map { |e| e[:type] }
+    assert_equal [:element_start,                          # api
+                  :attribute_tree_start,                   # :headers
+                  :element_start, :attribute, :element_end, # header[content-type]
+                  :element_start, :attribute, :element_end, # header[auth]
+                  :attribute_tree_end,
+                  :element_start, :element_end,            # body, child of api
+                  :element_end], types
+  end
+
+  def test_complex_attribute_key
+    events = Udon.parse("|api\n  :headers\n    |header\n")
+    assert_equal :attribute_tree_start, events[1][:type]
+    assert_equal "headers", events[1][:key]
+  end
+
+  def test_valueless_block_attribute_followed_by_sibling
+    input = <<~UDON
+      |api
+        :deprecated
+        :method POST
+    UDON
+    events = Udon.parse(input)
+    refute_includes events.map { |e| e[:type] }, :attribute_tree_start
+    attrs = events.select { |e| e[:type] == :attribute }
+    assert_equal %w[deprecated method], attrs.map { |e| e[:key] }
+  end
+
+  def test_valueless_sameline_attribute_followed_by_deeper_child
+    input = <<~UDON
+      |a :x
+             |b
+    UDON
+    events = Udon.parse(input)
+    refute_includes events.map { |e| e[:type] }, :attribute_tree_start
+    flag = events.find { |e| e[:type] == :attribute }
+    assert_equal "x", flag[:key]
+    assert_equal true, flag[:value]
+    assert_equal %w[+a +b - -], element_trace(input)
+  end
+
   def test_flag_attribute
     events = Udon.parse("|input :disabled\n")
     types = events.map { |e| e[:type] }
@@ -184,6 +286,284 @@ class UdonFFITest < Minitest::Test
     assert_equal expected, types
   end
 
+  # Hierarchy examples from FULL-SPEC.md "Hierarchy (Indentation and Columns)".
+  # Traces list "+name" per element_start and "-" per element_end.
+
+  def test_hierarchy_inline_nesting
+    assert_equal %w[+one +two +three - - -], element_trace("|one |two |three\n")
+  end
+
+  def test_hierarchy_inline_nesting_equals_vertical_form
+    vertical = <<~UDON
+      |one
+           |two
+                |three
+    UDON
+    assert_equal element_trace("|one |two |three\n"), element_trace(vertical)
+  end
+
+  def test_hierarchy_column_aligned_siblings
+    input = <<~UDON
+      |table |tr |td A1
+                 |td A2
+             |tr |td B1
+                 |td B2
+        |caption Table 1
+    UDON
+    expected = %w[+table +tr +td - +td - - +tr +td - +td - - +caption - -]
+    assert_equal expected, element_trace(input)
+  end
+
+  def test_hierarchy_sibling_after_inline_elements
+    input = "|one |two |three\n  |alpha\n"
+    assert_equal %w[+one +two +three - - +alpha - -], element_trace(input)
+  end
+
+  def test_hierarchy_column_alignment_is_sibling
+    input = "|one |two |three\n     |alpha\n"
+    assert_equal %w[+one +two +three - - +alpha - -], element_trace(input)
+  end
+
+  def test_hierarchy_same_column_sibling_next_column_child
+    input = <<~UDON
+      |parent
+        |child
+        |sibling
+         |inside
+    UDON
+    assert_equal %w[+parent +child - +sibling +inside - - -], element_trace(input)
+  end
+
+  def test_hierarchy_style_consistent_column_alignment
+    input = <<~UDON
+      |one |two |three
+           |better
+           |better
+    UDON
+    assert_equal %w[+one +two +three - - +better - +better - -], element_trace(input)
+  end
+
+  def test_hierarchy_style_consistent_indent
+    input = <<~UDON
+      |one |two |three
+        |also-good
+        |also-good
+    UDON
+    assert_equal %w[+one +two +three - - +also-good - +also-good - -], element_trace(input)
+  end
+
+  def test_hierarchy_style_inconsistent_sibling_columns
+    input = <<~UDON
+      |one |two |three
+           |alpha       ; chose column 5
+        |beta           ; but then used column 2
+    UDON
+    # Both are siblings of |two; mixing columns is poor form (W0102)
+    assert_equal %w[+one +two +three - - +alpha ; - +beta ; - -], element_trace(input)
+    events = Udon.parse(input)
+    assert_equal %w[W0102], events.select { |e| e[:type] == :warning }.map { |e| e[:code] }
+    assert_empty events.select { |e| e[:type] == :error }
+  end
+
+  def test_hierarchy_rule_visualized_comments_are_children
+    input = <<~UDON
+      |alpha |beta |theta
+                          ;<- where to put |gamma depends on who you want it to be siblings with
+                          ;   these comments are in fact children of |theta
+    UDON
+    assert_equal %w[+alpha +beta +theta ; ; - - -], element_trace(input)
+  end
+
+  def test_hierarchy_rule_visualized_gamma_placement
+    # |alpha at 0, |beta at 7, |theta at 13
+    { 1 => %w[+alpha +beta +theta - - +gamma - -],        # sibling of beta
+      6 => %w[+alpha +beta +theta - - +gamma - -],
+      7 => %w[+alpha +beta +theta - - +gamma - -],
+      8 => %w[+alpha +beta +theta - +gamma - - -],        # sibling of theta
+      13 => %w[+alpha +beta +theta - +gamma - - -],
+      14 => %w[+alpha +beta +theta +gamma - - - -] }.each do |col, expected| # child of theta
+      input = "|alpha |beta |theta
+#{' ' * col}|gamma
+"
+      assert_equal expected, element_trace(input), "gamma at column #{col}"
+    end
+  end
+
+  def test_hierarchy_python_perspective_vertical_form
+    vertical = <<~UDON
+      |alpha
+             |beta
+                   |c
+                      |d
+    UDON
+    assert_equal element_trace("|alpha |beta |c |d\n"), element_trace(vertical)
+  end
+
+  def test_hierarchy_python_perspective_e_placement
+    # |alpha at 0, |beta at 7, |c at 13, |d at 16
+    { 7 => %w[+alpha +beta +c +d - - - +e - -],           # sibling of beta
+      10 => %w[+alpha +beta +c +d - - +e - - -],          # child of beta, sibling of c
+      13 => %w[+alpha +beta +c +d - - +e - - -],          # sibling of c
+      15 => %w[+alpha +beta +c +d - +e - - - -],          # child of c, sibling of d
+      16 => %w[+alpha +beta +c +d - +e - - - -],          # sibling of d
+      17 => %w[+alpha +beta +c +d +e - - - - -] }.each do |col, expected| # child of d
+      input = "|alpha |beta |c |d\n#{' ' * col}|e\n"
+      assert_equal expected, element_trace(input), "e at column #{col}"
+    end
+  end
+
+  def test_hierarchy_child_of_inline_element
+    between = "|one |two |three\n        |alpha\n"
+    aligned = "|one |two |three\n          |alpha\n"
+    expected = %w[+one +two +three - +alpha - - -]
+    assert_equal expected, element_trace(between)
+    assert_equal expected, element_trace(aligned)
+  end
+
+  def test_hierarchy_multi_line_progression
+    input = <<~UDON
+      |one |two |three
+             |alpha
+           |beta
+    UDON
+    expected = %w[+one +two +three - +alpha - - +beta - -]
+    assert_equal expected, element_trace(input)
+  end
+
+  def test_hierarchy_only_previous_stack_matters
+    input = <<~UDON
+      |one |two |three
+        |alpha
+           |beta
+    UDON
+    expected = %w[+one +two +three - - +alpha +beta - - -]
+    assert_equal expected, element_trace(input)
+  end
+
+  def test_hierarchy_many_inline_elements
+    input = <<~UDON
+      |a |b |c |d |e |f |g
+               |child-of-c
+         |child-of-a
+    UDON
+    expected = %w[+a +b +c +d +e +f +g - - - - +child-of-c - - - +child-of-a - -]
+    assert_equal expected, element_trace(input)
+  end
+
+  def test_hierarchy_closing_multiple_levels
+    input = <<~UDON
+      |one
+        |two
+          |three
+            |four
+      - this prose is sibling to one
+    UDON
+    events = Udon.parse(input)
+    assert_equal %w[+one +two +three +four - - - -], element_trace(input)
+    assert_equal :text, events.last[:type]
+  end
+
+  def test_hierarchy_block_comment_at_column_zero_closes_elements
+    input = <<~UDON
+      |parent
+        |child
+          |grandchild
+      ; closes everything
+      |sibling
+    UDON
+    expected = %w[+parent +child +grandchild - - - ; +sibling -]
+    assert_equal expected, element_trace(input)
+  end
+
+  def test_hierarchy_block_comment_inside_element
+    input = <<~UDON
+      |element
+        Some prose content
+         ; comment inside |element
+        More prose content
+    UDON
+    assert_equal %w[+element ; -], element_trace(input)
+  end
+
+  # Embedded elements, FULL-SPEC.md "Inline and Embedded Elements".
+
+  def test_embedded_element_in_prose
+    events = Udon.parse("|p Click |{a :href /home here} to continue\n")
+    types = events.map { |e| e[:type] }
+    assert_equal [:element_start, :text, :element_start, :attribute, :text,
+                  :element_end, :text, :element_end], types
+    assert_equal "Click ", events[1][:content]
+    assert_equal "a", events[2][:name]
+    assert_equal :embedded, events[2][:form]
+    assert_equal "/home", events[3][:value]
+    assert_equal "here", events[4][:content]
+    assert_equal " to continue", events[6][:content]
+  end
+
+  def test_embedded_attribute_value_ends_at_brace
+    events = Udon.parse("|p |{a :href /home}\n")
+    attr = events.find { |e| e[:type] == :attribute }
+    assert_equal "/home", attr[:value]
+    assert_equal %w[+p +a - -], element_trace("|p |{a :href /home}\n")
+  end
+
+  def test_embedded_elements_nest
+    input = "|p See |{a :href /doc the |{em official} documentation} for details.\n"
+    assert_equal %w[+p +a +em - - -], element_trace(input)
+  end
+
+  def test_embedded_siblings
+    input = "|nav |{a :href / Home} |{a :href /about About}\n"
+    assert_equal %w[+nav +a - +a - -], element_trace(input)
+  end
+
+  def test_embedded_content_is_brace_balanced
+    events = Udon.parse("|p |{code f(x) { x }} done\n")
+    code = events.index { |e| e[:type] == :element_start && e[:name] == "code" }
+    assert_equal "f(x) { x }", events[code + 1][:content]
+    assert_equal %w[+p +code - -], element_trace("|p |{code f(x) { x }} done\n")
+  end
+
+  def test_embedded_element_spans_lines
+    input = <<~UDON
+      |p This has |{a :href /docs
+         a link that spans
+         multiple lines} and continues.
+    UDON
+    assert_equal %w[+p +a - -], element_trace(input)
+  end
+
+  def test_embedded_inline_comment
+    events = Udon.parse("|p Some text ;{TODO: improve this} and more text.\n")
+    comment = events.find { |e| e[:type] == :comment }
+    assert_equal "TODO: improve this", comment[:content]
+    text = events.select { |e| e[:type] == :text }.map { |e| e[:content] }.join
+    refute_includes text, "TODO"
+    assert text.end_with?("and more text.")
+  end
+
+  def test_embedded_backslash_escapes
+    events = Udon.parse("|p |{em text\\;more} and \\|{literal}\n")
+    text = events.select { |e| e[:type] == :text }.map { |e| e[:content] }.join
+    assert_equal "text;more and |{literal}", text
+    assert_equal %w[+p +em - -], element_trace("|p |{em text\\;more} and \\|{literal}\n")
+  end
+
+  def test_embedded_interpolation
+    events = Udon.parse("|p Hello |{strong !{{user.name}}}!\n")
+    assert_includes events.map { |e| e[:type] }, :interpolation
+    assert_equal %w[+p +strong - -], element_trace("|p Hello |{strong !{{user.name}}}!\n")
+  end
+
+  def test_parse_docbook_table_example
+    path = File.expand_path('../examples/docbook-fo-table.udon', __dir__)
+    skip "docbook-fo-table.udon not found" unless File.exist?(path)
+
+    events = Udon.parse(File.read(path))
+    errors = events.select { |e| e[:type] == :error }
+    assert_empty errors, errors.map { |e| "#{e[:message]} at #{e[:span]}" }.join("\n")
+  end
+
   def test_escape_pipe
     events = Udon.parse("'|not-element\n")
     types = events.map { |e| e[:type] }
@@ -198,6 +578,458 @@ class UdonFFITest < Minitest::Test
     assert_equal ";not-comment", events[0][:content]
   end
 
+  def test_block_escape_all_markers
+    { "'|element" => "|element", "';comment" => ";comment", "':attr" => ":attr",
+      "'!directive" => "!directive", "''more" => "'more" }.each do |input, output|
+      events = Udon.parse("#{input}\n")
+      assert_equal [:text], events.map { |e| e[:type] }, input
+      assert_equal output, events[0][:content], input
+    end
+  end
+
+  def test_block_escape_non_marker_keeps_apostrophe
+    events = Udon.parse("'hello\n")
+    assert_equal [:text], events.map { |e| e[:type] }
+    assert_equal "'hello", events[0][:content]
+  end
+
+  def test_block_escape_backslash_alternate
+    events = Udon.parse("\\|element\n")
+    assert_equal [:text], events.map { |e| e[:type] }
+    assert_equal "|element", events[0][:content]
+  end
+
+  def test_raw_block_preserves_content
+    input = <<~UDON
+      |example
+        !:elixir:
+          def hello do
+            IO.puts("world")
+            |> this_pipe_is_elixir_not_udon()
+
+          end
+        |after
+    UDON
+    events = Udon.parse(input)
+    start = events.find { |e| e[:type] == :directive_start }
+    assert_equal "elixir", start[:name]
+    assert_equal true, start[:raw]
+
+    body = events.select { |e| e[:type] == :raw_content }.map { |e| e[:content] }.join
+    expected = "def hello do\n  IO.puts(\"world\")\n  |> this_pipe_is_elixir_not_udon()\n\nend"
+    assert_equal expected, body.chomp
+    assert_equal %w[+example +after - -], element_trace(input)
+  end
+
+  def test_raw_block_semicolons_are_content
+    input = <<~UDON
+      !:sql:
+        SELECT * FROM users; -- not a UDON comment
+        WHERE active = true;
+    UDON
+    events = Udon.parse(input)
+    refute_includes events.map { |e| e[:type] }, :comment
+    body = events.select { |e| e[:type] == :raw_content }.map { |e| e[:content] }.join
+    assert_equal "SELECT * FROM users; -- not a UDON comment\nWHERE active = true;", body.chomp
+  end
+
+  def test_raw_inline_is_brace_counted
+    events = Udon.parse(%(|p The response was !{:json: {"status": "ok", "count": 42}} as expected.\n))
+    start = events.find { |e| e[:type] == :directive_start }
+    assert_equal "json", start[:name]
+    assert_equal true, start[:raw]
+    raw = events.select { |e| e[:type] == :raw_content }.map { |e| e[:content] }.join
+    assert_equal %({"status": "ok", "count": 42}), raw
+    assert_equal " as expected.", events.reverse.find { |e| e[:type] == :text }[:content]
+  end
+
+  def test_raw_inline_nested_braces
+    events = Udon.parse("|p !{:regex: [a-z]{3,5}}\n")
+    raw = events.select { |e| e[:type] == :raw_content }.map { |e| e[:content] }.join
+    assert_equal "[a-z]{3,5}", raw
+  end
+
+  def test_freeform_block_is_untouched
+    input = "|element and here we go with ```\nfreestyling it!\n  |not-an-element\n```\n"
+    events = Udon.parse(input)
+    body = events.select { |e| e[:type] == :freeform_content }.map { |e| e[:content] }.join
+    assert_equal "\nfreestyling it!\n  |not-an-element\n", body
+    assert_equal %w[+element -], element_trace(input)
+  end
+
+  def test_freeform_block_ignores_indentation
+    input = "|parent\n  |child\n    some content then ``` and now we're free\nanything goes\n    ```\n"
+    body = Udon.parse(input).select { |e| e[:type] == :freeform_content }.map { |e| e[:content] }.join
+    assert_equal " and now we're free\nanything goes\n", body
+    assert_equal %w[+parent +child - -], element_trace(input)
+  end
+
+  # Prose dedentation, FULL-SPEC.md "Automatic Prose Dedentation".
+
+  def test_prose_dedent_basic
+    input = <<~UDON
+      |section **The great indent**
+        This content is all inner-content of |section,
+        and will continue to be inner-content of |section
+        until the parser detects a dedent.
+    UDON
+    events = Udon.parse(input)
+    assert_equal ["**The great indent**",
+                  "This content is all inner-content of |section,",
+                  "and will continue to be inner-content of |section",
+                  "until the parser detects a dedent."], text_lines(events)
+    assert_empty events.select { |e| e[:type] == :warning }
+  end
+
+  def test_prose_dedent_inline_content_continuation
+    input = <<~UDON
+      |later-part This stuff is inner to |later-part
+                  and, with a slightly different formatting
+                  preference-- is indented quite a ways.
+    UDON
+    events = Udon.parse(input)
+    assert_equal ["This stuff is inner to |later-part",
+                  "and, with a slightly different formatting",
+                  "preference-- is indented quite a ways."], text_lines(events)
+    assert_empty events.select { |e| e[:type] == :warning }
+  end
+
+  def test_prose_dedent_line_two_chooses_base
+    ["|element-bigger Here's the first line\n                and here's an equally acceptable form\n",
+     "|element-bigger Here's another first line\n       This is also just as acceptable\n"].each do |input|
+      events = Udon.parse(input)
+      assert_empty events.select { |e| e[:type] == :warning }, input
+      refute text_lines(events).last.start_with?(" "), input
+    end
+  end
+
+  def test_prose_dedent_inconsistent_indentation_warns
+    input = <<~UDON
+      |the-parent |on-line-child
+            first-line-of-prose...
+         but what about this???
+         ^ this is the new reference
+         also not a new warning
+             four extra spaces
+        new warning here
+    UDON
+    events = Udon.parse(input)
+    assert_equal ["first-line-of-prose...",
+                  "but what about this???",
+                  "^ this is the new reference",
+                  "also not a new warning",
+                  "    four extra spaces",
+                  "new warning here"], text_lines(events).last(6)
+
+    warnings = events.select { |e| e[:type] == :warning }
+    assert_equal %w[W0101 W0101], warnings.map { |e| e[:code] }
+    assert_equal input.index("   but what"), warnings[0][:span].first
+    assert_equal input.index("  new warning"), warnings[1][:span].first
+
+    # Warning precedes the text of the line it is about
+    first_warning = events.index(warnings[0])
+    assert_equal "but what about this???", events[first_warning + 1][:content]
+  end
+
+  def test_prose_dedent_inconsistent_sibling_indent_warns
+    input = <<~UDON
+      |element-bigger Here's some child text |another-element
+                                             |child-of-bigger
+                     ; ^ sibling to another-element, child of element-bigger
+                   |also-child-of-bigger     ; WARNING - less indent than line 2
+    UDON
+    assert_equal %w[+element-bigger +another-element - +child-of-bigger - ; +also-child-of-bigger ; - -],
+                 element_trace(input)
+
+    events = Udon.parse(input)
+    warnings = events.select { |e| e[:type] == :warning }
+    assert_equal %w[W0102], warnings.map { |e| e[:code] }
+    assert_equal input.index("             |also-child"), warnings[0][:span].first
+  end
+
+  def test_prose_dedent_extra_spaces_preserved_without_warning
+    input = <<~UDON
+      |element-bigger and some child text |and-another inner text here
+                                    This is also a direct child of element-bigger,
+                                        just in a very unconventional spot.
+    UDON
+    events = Udon.parse(input)
+    assert_includes text_lines(events), "    just in a very unconventional spot."
+    assert_empty events.select { |e| e[:type] == :warning }
+  end
+
+  def test_prose_dedent_blank_lines_pass_through
+    input = "|p\n  first paragraph\n\n  second paragraph\n"
+    assert_equal ["first paragraph", "", "second paragraph"], text_lines(Udon.parse(input))
+  end
+
+  def test_number_integer_forms
+    { "42" => 42, "-42" => -42, "1_000_000" => 1_000_000, "0xFF" => 255,
+      "0o755" => 493, "0b1010" => 10, "0d755" => 755, "0755" => 755,
+      "-0x10" => -16 }.each do |literal, value|
+      attr = Udon.parse("|x :v #{literal}\n").find { |e| e[:type] == :attribute }
+      assert_equal :integer, attr[:value_type], literal
+      assert_equal value, attr[:value], literal
+    end
+  end
+
+  def test_number_integer_has_no_size_limit
+    literal = "18446744073709551616"
+    attr = Udon.parse("|x :v #{literal}\n").find { |e| e[:type] == :attribute }
+    assert_equal :integer, attr[:value_type]
+    assert_equal 2**64, attr[:value]
+  end
+
+  def test_number_float_keeps_lexeme
+    { "19.99" => 19.99, "0.3" => 0.3, "1.5e-3" => 1.5e-3, "1_000.5" => 1000.5 }.each do |literal, value|
+      attr = Udon.parse("|x :v #{literal}\n").find { |e| e[:type] == :attribute }
+      assert_equal :float, attr[:value_type], literal
+      assert_equal value, attr[:value], literal
+      assert_equal literal, attr[:lexeme], literal
+    end
+  end
+
+  def test_number_rational_kept_as_written
+    attr = Udon.parse("|x :v 2/4r\n").find { |e| e[:type] == :attribute }
+    assert_equal :rational, attr[:value_type]
+    assert_equal Rational(1, 2), attr[:value]
+    assert_equal "2/4r", attr[:lexeme]
+  end
+
+  def test_number_rational_zero_denominator_warns
+    input = "|x :v 1/0r\n"
+    events = Udon.parse(input)
+    attr_index = events.index { |e| e[:type] == :attribute }
+    assert_equal :string, events[attr_index][:value_type]
+    assert_equal "1/0r", events[attr_index][:value]
+    assert_equal :warning, events[attr_index - 1][:type]
+    assert_equal input.index("1/0r"), events[attr_index - 1][:span].first
+  end
+
+  def test_number_complex_forms
+    { "3+4i" => Complex(3, 4), "5i" => Complex(0, 5), "1.5+2i" => Complex(1.5, 2),
+      "3-0.5i" => Complex(3, -0.5), "2025-4i" => Complex(2025, -4),
+      "2025-1i" => Complex(2025, -1) }.each do |literal, value|
+      events = Udon.parse("|x :v #{literal}\n")
+      attr = events.find { |e| e[:type] == :attribute }
+      assert_equal :complex, attr[:value_type], literal
+      assert_equal value, attr[:value], literal
+      assert_empty events.select { |e| e[:type] == :warning }, literal
+    end
+  end
+
+  def test_number_misplaced_underscores_are_strings
+    %w[1__000 _1 1_ 0x_FF].each do |literal|
+      attr = Udon.parse("|x :v #{literal}\n").find { |e| e[:type] == :attribute }
+      assert_equal :string, attr[:value_type], literal
+      assert_equal literal, attr[:value], literal
+    end
+  end
+
+  def test_parse_billing_example
+    path = File.expand_path('../examples/ash-like-billing.udon', __dir__)
+    skip "ash-like-billing.udon not found" unless File.exist?(path)
+
+    events = Udon.parse(File.read(path))
+    errors = events.select { |e| e[:type] == :error }
+    assert_empty errors, errors.map { |e| "#{e[:message]} at #{e[:span]}" }.join("\n")
+
+    attrs = events.select { |e| e[:type] == :attribute }
+    version = attrs.find { |e| e[:key] == "version" }
+    assert_equal :float, version[:value_type]
+    assert_equal "0.3", version[:lexeme]
+  end
+
+  def test_number_billing_rate_keeps_exact_digits
+    input = "|attr[tax-rate].decimal :default 0.0825 :max 1\n"
+    attrs = Udon.parse(input).select { |e| e[:type] == :attribute }
+    assert_equal "0.0825", attrs.find { |e| e[:key] == "default" }[:lexeme]
+    assert_equal :integer, attrs.find { |e| e[:key] == "max" }[:value_type]
+  end
+
+  def test_temporal_values_are_typed
+    # TIME-SPEC.md: typed events carry the original text unchanged
+    { "2025-01-03" => :date,
+      "2025-12" => :year_month,
+      "14:30" => :time,
+      "14:30:00.123456789012" => :time,
+      "24:00:00" => :time,
+      "2025-01-03T14:30:00Z" => :date_time,
+      "2025-01-03T20:00:00+05:30" => :date_time,
+      "2025-01-03T14:30:00-00:00" => :date_time,
+      "P1Y2M3DT4H5M6S" => :duration,
+      "PT1.5H" => :duration,
+      "P2W" => :duration,
+      "90m" => :duration,
+      "1.5h" => :duration,
+      "30S" => :duration,
+      "+30d" => :relative_time,
+      "-P1Y2M3D" => :relative_time }.each do |literal, type|
+      attr = Udon.parse("|x :v #{literal}\n").find { |e| e[:type] == :attribute }
+      assert_equal type, attr[:value_type], literal
+      assert_equal literal, attr[:value], literal
+    end
+  end
+
+  def test_temporal_year_alone_is_integer
+    attr = Udon.parse("|x :year 2025\n").find { |e| e[:type] == :attribute }
+    assert_equal :integer, attr[:value_type]
+  end
+
+  def test_temporal_minutes_vs_months
+    { "5m" => { minutes: 5 }, "5mo" => { months: 5 },
+      "5M" => { minutes: 5 }, "5MO" => { months: 5 }, "5Mo" => { months: 5 } }.each do |literal, parts|
+      attr = Udon.parse("|x :v #{literal}\n").find { |e| e[:type] == :attribute }
+      assert_equal :duration, attr[:value_type], literal
+      assert_equal parts, attr[:parts], literal
+    end
+  end
+
+  def test_temporal_invalid_ending_is_bare_string
+    literal = "2026-03-24T03:12:4.993290109288-says-what"
+    events = Udon.parse("|x :v #{literal}\n")
+    attr = events.find { |e| e[:type] == :attribute }
+    assert_equal :string, attr[:value_type]
+    assert_equal literal, attr[:value]
+    assert_empty events.select { |e| e[:type] == :warning }
+  end
+
+  def test_temporal_out_of_range_is_bare_string
+    %w[2025-13-01 2025-01-32 24:30 14:60].each do |literal|
+      events = Udon.parse("|x :v #{literal}\n")
+      assert_equal :string, events.find { |e| e[:type] == :attribute }[:value_type], literal
+      assert_empty events.select { |e| e[:type] == :warning }, literal
+    end
+  end
+
+  def test_temporal_near_misses_warn
+    %w[2025-1-3 9:30 P1W2D P2WT4H PT1.5H30M P1.5DT2H 1h30m].each do |literal|
+      input = "|x :v #{literal}\n"
+      events = Udon.parse(input)
+      attr_index = events.index { |e| e[:type] == :attribute }
+      assert_equal :string, events[attr_index][:value_type], literal
+      assert_equal literal, events[attr_index][:value], literal
+
+      warning = events[attr_index - 1]
+      assert_equal :warning, warning[:type], literal
+      assert_equal input.index(literal), warning[:span].first, literal
+    end
+  end
+
+  def test_temporal_non_matches_do_not_warn
+    %w[P PT 2025-1-apples 1d12hours].each do |literal|
+      events = Udon.parse("|x :v #{literal}\n")
+      assert_equal :string, events.find { |e| e[:type] == :attribute }[:value_type], literal
+      assert_empty events.select { |e| e[:type] == :warning }, literal
+    end
+  end
+
+  def test_interpolation_in_attribute_value_alone
+    attr = Udon.parse("|link :href !{{computed_url}}\n").find { |e| e[:type] == :attribute }
+    assert_equal :interpolation, attr[:value_type]
+    assert_equal "computed_url", attr[:value][:expression]
+  end
+
+  def test_interpolation_in_attribute_value_mixed
+    attr = Udon.parse("|link :href !{{base}}/users/!{{user.id}}\n").find { |e| e[:type] == :attribute }
+    assert_equal :concat, attr[:value_type]
+    assert_equal [{ type: :interpolation, expression: "base" },
+                  "/users/",
+                  { type: :interpolation, expression: "user.id" }], parts(attr[:value])
+  end
+
+  def test_interpolation_in_element_id
+    events = Udon.parse("|div[prefix_!{{id}}_suffix]\n")
+    assert_equal "div", events[0][:name]
+    assert_equal ["prefix_", { type: :interpolation, expression: "id" }, "_suffix"], parts(events[0][:id])
+
+    events = Udon.parse("|div[!{{dynamic_id}}]\n")
+    assert_equal "dynamic_id", events[0][:id][:expression]
+  end
+
+  def test_interpolation_interrupting_number_makes_string
+    events = Udon.parse("|item[283!{{more}}] :port 80!{{offset}}\n")
+    assert_equal ["283", { type: :interpolation, expression: "more" }], parts(events[0][:id])
+    attr = events.find { |e| e[:type] == :attribute }
+    assert_equal ["80", { type: :interpolation, expression: "offset" }], parts(attr[:value])
+  end
+
+  def test_interpolation_in_quoted_string_is_literal
+    attr = Udon.parse("|x :note \"!{{x}}\"\n").find { |e| e[:type] == :attribute }
+    assert_equal :string, attr[:value_type]
+    assert_equal "!{{x}}", attr[:value]
+  end
+
+  def test_interpolation_spaces_do_not_end_value
+    attr = Udon.parse("|a :href !{{ base }}/x :rel next\n").find { |e| e[:key] == "href" }
+    assert_equal [{ type: :interpolation, expression: " base " }, "/x"], parts(attr[:value])
+  end
+
+  def test_interpolation_adjacent_has_no_empty_segment
+    attr = Udon.parse("|x :v !{{a}}!{{b}}\n").find { |e| e[:type] == :attribute }
+    assert_equal %w[a b], attr[:value].map { |p| p[:expression] }
+  end
+
+  def test_interpolation_in_list_items
+    attr = Udon.parse("|x :v [a !{{b}} c!{{d}}]\n").find { |e| e[:type] == :attribute }
+    assert_equal :list, attr[:value_type]
+    a, b, cd = attr[:value]
+    assert_equal "a", a
+    assert_equal "b", b[:expression]
+    assert_equal ["c", { type: :interpolation, expression: "d" }], parts(cd)
+  end
+
+  def test_interpolation_unclosed_in_value_is_error
+    events = Udon.parse("|x :v abc!{{oops\n")
+    assert events.any? { |e| e[:type] == :error }, "Expected error for unclosed interpolation"
+  end
+
+  def test_expr_path_and_filter_chain
+    expr = Udon.parse_expression("value | filter1 | filter2 arg")
+    assert_equal({ type: :path, segments: ["value"] }, expr[:input].slice(:type, :segments))
+    assert_equal %w[filter1 filter2], expr[:filters].map { |f| f[:name] }
+    assert_equal ["arg"], expr[:filters][1][:args].map { |a| a[:segments] }.flatten
+  end
+
+  def test_expr_paths_with_indexes
+    expr = Udon.parse_expression("rows[0].cells[\"total\"]")
+    assert_equal ["rows", 0, "cells", "total"], expr[:input][:segments]
+    assert_empty expr[:filters]
+  end
+
+  def test_expr_arguments_use_value_typing
+    expr = Udon.parse_expression(%(d | truncate 20 "…" | shift 30d | default fallback: "n/a"))
+    truncate, shift, default = expr[:filters]
+    assert_equal [[:integer, 20], [:string, "…"]], truncate[:args].map { |a| [a[:value_type], a[:value]] }
+    assert_equal :duration, shift[:args][0][:value_type]
+    assert_equal "fallback", default[:args][0][:key]
+  end
+
+  def test_expr_liquid_argument_form_is_equivalent
+    spaced = Udon.parse_expression(%(s | truncate 20 "…"))[:filters][0][:args]
+    liquid = Udon.parse_expression(%(s | truncate: 20, "…"))[:filters][0][:args]
+    assert_equal spaced.map { |a| a[:value] }, liquid.map { |a| a[:value] }
+  end
+
+  def test_expr_quoted_pipe_is_content
+    expr = Udon.parse_expression(%(x | append " | " | upcase))
+    assert_equal %w[append upcase], expr[:filters].map { |f| f[:name] }
+    assert_equal " | ", expr[:filters][0][:args][0][:value]
+  end
+
+  def test_expr_spans_are_document_offsets
+    input = "|p Hi !{{name | capitalize}}\n"
+    interp = Udon.parse(input).find { |e| e[:type] == :interpolation }
+    expr = Udon.parse_expression(interp[:expression], interp[:expression_span].first)
+    assert_equal input.index("capitalize"), expr[:filters][0][:name_span].first
+  end
+
+  def test_expr_empty_filter_is_error
+    error = assert_raises(Udon::ExpressionError) { Udon.parse_expression("a | | b") }
+    assert_equal "E0401", error.code
+    assert_equal 4, error.span.first
+  end
+
   def test_pipe_as_prose
     # | followed by space is prose, not element
     events = Udon.parse("a | b\n")
@@ -244,4 +1076,25 @@ class UdonFFITest < Minitest::Test
     events = Udon.each_event(input).to_a
     assert_equal 4, events.size # a start, a end, b start, b end
   end
+
+  private
+
+  def text_lines(events)
+    events.select { |e| e[:type] == :text }.map { |e| e[:content] }
+  end
+
+  # Concat values as plain strings and {type:, expression:} pairs
+  def parts(value)
+    value.map { |p| p.is_a?(Hash) ? p.slice(:type, :expression) : p }
+  end
+
+  def element_trace(input)
+    Udon.parse(input).filter_map do |e|
+      case e[:type]
+      when :element_start then "+#{e[:name]}"
+      when :element_end then "-"
+      when :comment then ";"
+      end
+    end
+  end
 end